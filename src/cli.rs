@@ -0,0 +1,69 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+
+/// dotscan: see which files in a directory are tracked by a bare dotfiles repo.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Cli {
+    /// Path to the bare git repository (e.g. ~/dotfiles)
+    #[arg(long, value_name = "DIR")]
+    pub git_dir: Option<PathBuf>,
+
+    /// Path to the work tree the bare repo is checked out against (e.g. ~)
+    #[arg(long, value_name = "DIR")]
+    pub work_tree: Option<PathBuf>,
+
+    /// Directories to scan. Defaults to the current directory.
+    #[arg(value_name = "TARGET")]
+    pub targets: Vec<PathBuf>,
+
+    /// Don't print entries for untracked files
+    #[arg(long)]
+    pub hide_untracked: bool,
+
+    /// Maximum depth to descend into each target directory. Unset means unlimited.
+    #[arg(long, value_name = "N")]
+    pub level: Option<usize>,
+
+    /// Disable colored output, even when stdout is a TTY
+    #[arg(long)]
+    pub no_color: bool,
+
+    /// Show gitignored entries (marked IGNORED) instead of omitting them
+    #[arg(long)]
+    pub include_ignored: bool,
+
+    /// Number of threads to scan with. Defaults to rayon's global pool size;
+    /// pass 1 for deterministic single-threaded scanning.
+    #[arg(long, value_name = "N")]
+    pub threads: Option<usize>,
+
+    /// Glyph for tracked, unmodified files
+    #[arg(long, value_name = "CHAR", help_heading = "Symbols")]
+    pub symbol_clean: Option<String>,
+
+    /// Glyph for untracked files
+    #[arg(long, value_name = "CHAR", help_heading = "Symbols")]
+    pub symbol_untracked: Option<String>,
+
+    /// Glyph for tracked files with uncommitted modifications
+    #[arg(long, value_name = "CHAR", help_heading = "Symbols")]
+    pub symbol_modified: Option<String>,
+
+    /// Glyph for files with staged changes
+    #[arg(long, value_name = "CHAR", help_heading = "Symbols")]
+    pub symbol_staged: Option<String>,
+
+    /// Glyph for files tracked in HEAD but missing from the work tree
+    #[arg(long, value_name = "CHAR", help_heading = "Symbols")]
+    pub symbol_deleted: Option<String>,
+
+    /// Glyph for renamed files
+    #[arg(long, value_name = "CHAR", help_heading = "Symbols")]
+    pub symbol_renamed: Option<String>,
+
+    /// Glyph for gitignored entries
+    #[arg(long, value_name = "CHAR", help_heading = "Symbols")]
+    pub symbol_ignored: Option<String>,
+}
@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+use std::ffi::OsString;
+#[cfg(unix)]
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use git2::{
+    ObjectType, Repository, Status, StatusEntry, StatusOptions, TreeWalkMode, TreeWalkResult,
+};
+
+/// The working-tree status of a file that the bare repo already tracks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileStatus {
+    /// Tracked, matches HEAD, no staged changes.
+    Clean,
+    /// Tracked, has uncommitted modifications in the work tree.
+    Modified,
+    /// Has changes staged in the index.
+    Staged,
+    /// Tracked in HEAD but missing from the work tree.
+    Deleted,
+    /// Tracked but renamed relative to HEAD.
+    Renamed,
+}
+
+/// Open the bare repo at `git_dir` against an explicit `work_tree`.
+pub fn open_repo(git_dir: &Path, work_tree: &Path) -> Result<Repository> {
+    let repo = Repository::open_bare(git_dir)
+        .with_context(|| format!("Failed to open bare repo at {}", git_dir.display()))?;
+    repo.set_workdir(work_tree, false)
+        .with_context(|| format!("Failed to set work tree to {}", work_tree.display()))?;
+    Ok(repo)
+}
+
+/// Walk HEAD's tree and return every tracked blob's path, relative to the
+/// work tree root.
+///
+/// Built from raw entry bytes rather than `TreeEntry::name` so filenames
+/// that aren't valid UTF-8 are still tracked correctly instead of being
+/// silently dropped.
+pub fn get_tracked_file_names(repo: &Repository) -> Result<Vec<PathBuf>> {
+    let head_tree = repo
+        .head()
+        .context("Failed to resolve HEAD")?
+        .peel_to_tree()
+        .context("Failed to peel HEAD to a tree")?;
+
+    let mut paths = Vec::new();
+    head_tree
+        .walk(TreeWalkMode::PreOrder, |root, entry| {
+            if entry.kind() == Some(ObjectType::Blob) {
+                paths.push(Path::new(root).join(os_string_from_bytes(entry.name_bytes())));
+            }
+            TreeWalkResult::Ok
+        })
+        .context("Failed to walk HEAD tree")?;
+
+    Ok(paths)
+}
+
+#[cfg(unix)]
+fn os_string_from_bytes(bytes: &[u8]) -> OsString {
+    std::ffi::OsStr::from_bytes(bytes).to_os_string()
+}
+
+#[cfg(not(unix))]
+fn os_string_from_bytes(bytes: &[u8]) -> OsString {
+    OsString::from(String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// Diff the index and work tree against HEAD once and return a map from
+/// work-tree-relative path to its `FileStatus`.
+///
+/// Paths that are clean (tracked and unchanged) never show up in git's
+/// status, so they're simply absent from the returned map; callers should
+/// treat a missing entry as `FileStatus::Clean`.
+pub fn get_file_statuses(repo: &Repository) -> Result<HashMap<PathBuf, FileStatus>> {
+    // Workdir rename detection only pairs a deleted tracked path with an
+    // untracked one if untracked files are actually scanned, so untracked
+    // entries have to stay on here despite `get_file_statuses` not caring
+    // about them otherwise; `classify` maps anything that isn't a rename,
+    // delete, or modification to `None` and they're dropped below.
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(false)
+        .renames_head_to_index(true)
+        .renames_index_to_workdir(true);
+
+    let statuses = repo
+        .statuses(Some(&mut options))
+        .context("Failed to read repo status")?;
+
+    let mut by_path = HashMap::new();
+    for entry in statuses.iter() {
+        let Some(file_status) = classify(entry.status()) else {
+            continue;
+        };
+
+        // `StatusEntry::path()` reports the *old* side of a rename (see
+        // `path_bytes` in git2), but the name that's actually on disk —
+        // and the one every other lookup in this program keys on — is
+        // the new one. Pull that off the delta instead for renames.
+        let path = if file_status == FileStatus::Renamed {
+            renamed_path(&entry)
+        } else {
+            entry.path().map(PathBuf::from)
+        };
+
+        if let Some(path) = path {
+            by_path.insert(path, file_status);
+        }
+    }
+
+    Ok(by_path)
+}
+
+/// The new-side path of a rename, preferring the staged (`head_to_index`)
+/// delta over the workdir (`index_to_workdir`) one, mirroring the
+/// precedence `StatusEntry::path_bytes` itself uses for the old side.
+fn renamed_path(entry: &StatusEntry) -> Option<PathBuf> {
+    let delta = entry.head_to_index().or_else(|| entry.index_to_workdir())?;
+    delta.new_file().path().map(PathBuf::from)
+}
+
+/// Map a single `git2::Status` bitflag set to the `FileStatus` dotscan
+/// reports, or `None` for a clean entry (which `git status` wouldn't have
+/// surfaced in the first place, but is handled here too for completeness).
+fn classify(status: Status) -> Option<FileStatus> {
+    if status.intersects(Status::INDEX_RENAMED | Status::WT_RENAMED) {
+        Some(FileStatus::Renamed)
+    } else if status.contains(Status::WT_DELETED) {
+        Some(FileStatus::Deleted)
+    } else if status.intersects(
+        Status::INDEX_NEW
+            | Status::INDEX_MODIFIED
+            | Status::INDEX_DELETED
+            | Status::INDEX_TYPECHANGE,
+    ) {
+        Some(FileStatus::Staged)
+    } else if status.intersects(Status::WT_MODIFIED | Status::WT_TYPECHANGE) {
+        Some(FileStatus::Modified)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rename_takes_precedence_over_everything_else() {
+        assert_eq!(
+            classify(Status::WT_RENAMED | Status::WT_MODIFIED),
+            Some(FileStatus::Renamed)
+        );
+        assert_eq!(classify(Status::INDEX_RENAMED), Some(FileStatus::Renamed));
+    }
+
+    #[test]
+    fn worktree_deletion_is_deleted() {
+        assert_eq!(classify(Status::WT_DELETED), Some(FileStatus::Deleted));
+    }
+
+    #[test]
+    fn index_changes_are_staged() {
+        assert_eq!(classify(Status::INDEX_NEW), Some(FileStatus::Staged));
+        assert_eq!(classify(Status::INDEX_MODIFIED), Some(FileStatus::Staged));
+        assert_eq!(classify(Status::INDEX_DELETED), Some(FileStatus::Staged));
+    }
+
+    #[test]
+    fn worktree_edits_without_staging_are_modified() {
+        assert_eq!(classify(Status::WT_MODIFIED), Some(FileStatus::Modified));
+        assert_eq!(classify(Status::WT_TYPECHANGE), Some(FileStatus::Modified));
+    }
+
+    #[test]
+    fn staged_changes_win_over_worktree_modifications() {
+        assert_eq!(
+            classify(Status::INDEX_MODIFIED | Status::WT_MODIFIED),
+            Some(FileStatus::Staged)
+        );
+    }
+
+    #[test]
+    fn nothing_set_is_clean() {
+        assert_eq!(classify(Status::CURRENT), None);
+    }
+}
@@ -0,0 +1,105 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+/// Build a gitignore matcher rooted at `work_tree`, combining the work
+/// tree's top-level `.gitignore`, every `.gitignore` along the path from
+/// `work_tree` down to `target`, and every `.gitignore` discovered while
+/// walking `target` itself — in parent-to-child order so patterns in a
+/// nested `.gitignore` take precedence over shallower ones, matching
+/// git's own precedence rules. The matcher is rooted at `work_tree` (not
+/// `target`) so anchored patterns like `/secrets` resolve against the
+/// same root git itself would use.
+pub fn build_matcher(work_tree: &Path, target: &Path) -> Result<Gitignore> {
+    let mut builder = GitignoreBuilder::new(work_tree);
+
+    let work_tree_gitignore = work_tree.join(".gitignore");
+    add_if_present(&mut builder, &work_tree_gitignore)?;
+
+    for ancestor in ancestor_gitignores(work_tree, target) {
+        add_if_present(&mut builder, &ancestor)?;
+    }
+
+    for nested in find_nested_gitignores(target) {
+        if nested != work_tree_gitignore {
+            add_if_present(&mut builder, &nested)?;
+        }
+    }
+
+    builder.build().context("Failed to build gitignore matcher")
+}
+
+/// Collect every `.gitignore` in the directories strictly between
+/// `work_tree` and `target` (exclusive of both endpoints, which are
+/// handled separately), in parent-to-child order.
+fn ancestor_gitignores(work_tree: &Path, target: &Path) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    let Ok(relative) = target.strip_prefix(work_tree) else {
+        return found;
+    };
+
+    let mut current = work_tree.to_path_buf();
+    for component in relative.components() {
+        current.push(component);
+        if current == target {
+            break;
+        }
+        let gitignore = current.join(".gitignore");
+        if gitignore.is_file() {
+            found.push(gitignore);
+        }
+    }
+
+    found
+}
+
+fn add_if_present(builder: &mut GitignoreBuilder, path: &Path) -> Result<()> {
+    if path.is_file() {
+        if let Some(err) = builder.add(path) {
+            return Err(err).with_context(|| format!("Failed to parse {}", path.display()));
+        }
+    }
+    Ok(())
+}
+
+/// Recursively collect every `.gitignore` under `dir` (inclusive), in
+/// parent-before-child order.
+fn find_nested_gitignores(dir: &Path) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    collect_gitignores(dir, &mut found);
+    found
+}
+
+fn collect_gitignores(dir: &Path, found: &mut Vec<PathBuf>) {
+    let gitignore = dir.join(".gitignore");
+    if gitignore.is_file() {
+        found.push(gitignore);
+    }
+
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in read_dir.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        if entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false) {
+            collect_gitignores(&path, found);
+        }
+    }
+}
+
+/// Whether `path` (relative to the work tree `matcher` was rooted at) is
+/// ignored, following standard gitignore precedence (later patterns win,
+/// `!` negates). Checks `path`'s parents too, so a file under an ignored
+/// directory is reported ignored even when nothing matches the file
+/// itself directly — matching how git itself treats ignored directories.
+/// A rooted path (one that lies outside the work tree) is never ignored.
+pub fn is_ignored(matcher: &Gitignore, path: &Path, is_dir: bool) -> bool {
+    if path.has_root() {
+        return false;
+    }
+    matcher
+        .matched_path_or_any_parents(path, is_dir)
+        .is_ignore()
+}
@@ -1,155 +1,197 @@
+mod cli;
+mod git;
+mod gitignore;
+mod scan;
+mod style;
+
 use anyhow::{anyhow, Context, Result};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::env;
 use std::path::{Path, PathBuf};
-use std::{env, process::Command};
+
+use clap::Parser;
+use cli::Cli;
+use git::FileStatus;
+use scan::{Entry, TrackedIndex};
+use style::{Formatter, Symbols};
 
 fn main() -> Result<()> {
-    let current_dir = env::current_dir()?;
+    let cli = Cli::parse();
+    let formatter = Formatter::new(Symbols::from_cli(&cli), cli.no_color);
+
     let home_dir = dirs::home_dir().ok_or(anyhow!("Failed to get home dir"))?;
 
-    let mut git_dir = PathBuf::from(&home_dir);
-    git_dir.push("dotfiles");
+    let git_dir = cli.git_dir.unwrap_or_else(|| home_dir.join("dotfiles"));
+    let work_tree = cli.work_tree.unwrap_or(home_dir);
 
-    let work_tree = &home_dir;
+    if !git_dir.exists() {
+        return Err(anyhow!(
+            "Bare repo path {} does not exist",
+            git_dir.display()
+        ));
+    }
 
-    let current_dir_file_names =
-        get_file_names(&current_dir).context("Failed to get file names in current dir")?;
-    let tracked_file_names = get_tracked_file_names(&current_dir, &git_dir, work_tree)
-        .context("Failed to get tracked file names")?;
-    let current_dir_tracked = count_tracked_files(tracked_file_names);
-    report_tracking_status(current_dir_file_names, &current_dir_tracked);
+    // Canonicalize so `target.strip_prefix(work_tree)` below is comparing
+    // two absolute paths rooted the same way, regardless of whether the
+    // user passed a relative target (`dotscan sub`) or a work tree/target
+    // that runs through a symlink.
+    let work_tree = work_tree
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve work tree {}", work_tree.display()))?;
+
+    let targets = if cli.targets.is_empty() {
+        vec![env::current_dir()?]
+    } else {
+        cli.targets
+    };
+    let targets = targets
+        .into_iter()
+        .map(|target| {
+            target
+                .canonicalize()
+                .with_context(|| format!("Failed to resolve target {}", target.display()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let repo = git::open_repo(&git_dir, &work_tree).context("Failed to open bare repo")?;
+    let tracked_file_names =
+        git::get_tracked_file_names(&repo).context("Failed to get tracked file names")?;
+    let tracked = TrackedIndex::build(tracked_file_names);
+    let statuses = git::get_file_statuses(&repo).context("Failed to get working-tree status")?;
+
+    let ctx = ReportContext {
+        formatter: &formatter,
+        tracked: &tracked,
+        statuses: &statuses,
+        work_tree: &work_tree,
+        hide_untracked: cli.hide_untracked,
+        include_ignored: cli.include_ignored,
+    };
+
+    for target in targets {
+        let mut target_entries = scan::get_file_names(&target, cli.level, cli.threads)
+            .context("Failed to get file names in target dir")?;
+        target_entries.extend(missing_tracked_entries(
+            &ctx,
+            &target,
+            &target_entries,
+            cli.level,
+        ));
+        target_entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let matcher = gitignore::build_matcher(&work_tree, &target)
+            .context("Failed to build gitignore matcher")?;
+        report_tracking_status(&ctx, &target, &target_entries, &matcher);
+    }
 
     Ok(())
 }
 
-fn get_file_names<P: AsRef<Path>>(dir: P) -> Result<impl Iterator<Item = String>> {
-    let output = Command::new("ls")
-        .args(["-p"]) // make dir end with "/"
-        .current_dir(dir)
-        .output()
-        .context("Failed to get ls output")?;
-    let stdout = String::from_utf8(output.stdout)?;
-    let file_names: Vec<_> = stdout
-        .split("\n")
-        .filter_map(|s| {
-            if s.len() > 0 {
-                Some(String::from(s))
-            } else {
-                None
+/// `entries` only ever contains what `fs::read_dir` found on disk, so a
+/// tracked file that's been deleted out from under the work tree has
+/// nothing to print it — it's absent from the walk, not merely unmatched.
+/// (A rename doesn't need this: `get_file_statuses` keys it by the new,
+/// still-on-disk path, which the walk already finds.) Fill that gap with
+/// a synthetic `Entry` per `Deleted` status whose work-tree-relative path
+/// falls under `target`, skipping any that the real walk would have
+/// pruned for lying past `max_depth` — its ancestor directories wouldn't
+/// be printed either in that case.
+fn missing_tracked_entries(
+    ctx: &ReportContext,
+    target: &Path,
+    entries: &[Entry],
+    max_depth: Option<usize>,
+) -> Vec<Entry> {
+    let present: HashSet<&Path> = entries.iter().map(|entry| entry.path.as_path()).collect();
+
+    ctx.statuses
+        .iter()
+        .filter(|(_, status)| **status == FileStatus::Deleted)
+        .filter_map(|(path, _)| {
+            let relative = ctx
+                .work_tree
+                .join(path)
+                .strip_prefix(target)
+                .ok()?
+                .to_path_buf();
+            if present.contains(relative.as_path()) {
+                return None;
             }
-        })
-        .collect();
-    Ok(file_names.into_iter())
-}
-
-fn get_tracked_file_names<P: AsRef<Path>>(
-    dir: P,
-    git_dir: P,
-    work_tree: P,
-) -> Result<impl Iterator<Item = String>> {
-    let output = Command::new("git")
-        .args([
-            &format!("--git-dir={}", git_dir.as_ref().display()),
-            &format!("--work-tree={}", work_tree.as_ref().display()),
-            "ls-tree",
-            "--name-only",
-            "-r",
-            "HEAD",
-        ])
-        .current_dir(dir)
-        .output()
-        .context("Failed to use git ls-tree to list tracked files")?;
-    let stdout = String::from_utf8(output.stdout)?;
-    let file_names: Vec<_> = stdout
-        .split("\n")
-        .filter_map(|s| {
-            if s.len() > 0 {
-                Some(String::from(s))
-            } else {
-                None
+            // The walk always lists the target's direct children, even at `--level 0`.
+            if max_depth.is_some_and(|limit| relative.components().count() > limit.max(1)) {
+                return None;
             }
+            Some(Entry {
+                path: relative,
+                is_dir: false,
+            })
         })
-        .collect();
-    Ok(file_names.into_iter())
+        .collect()
 }
 
-// count tracked files when encounter a directory prefix
-// count = 1 when encounter a bare file name
-fn count_tracked_files<I>(file_names_iter: I) -> HashMap<String, u32>
-where
-    I: IntoIterator,
-    I::Item: AsRef<str>,
-{
-    let mut h = HashMap::<String, u32>::new();
-    let mut increment_tracked = |dir_or_file: String| {
-        h.entry(dir_or_file)
-            .and_modify(|counter| *counter += 1)
-            .or_insert(1);
-    };
-
-    for file_name in file_names_iter {
-        let mut split_by_dir = file_name.as_ref().split("/");
-        match (split_by_dir.next(), split_by_dir.next()) {
-            (Some(dir_name), Some(_)) => increment_tracked(format!("{}/", dir_name)),
-            (Some(file_name), None) => increment_tracked(String::from(file_name)),
-            (None, _) => {
-                unreachable!("split_by_dir should at least have one item")
-            }
-        }
-    }
-
-    h
+/// Everything `report_tracking_status` needs that stays the same across
+/// every target directory.
+struct ReportContext<'a> {
+    formatter: &'a Formatter,
+    tracked: &'a TrackedIndex,
+    statuses: &'a HashMap<PathBuf, FileStatus>,
+    work_tree: &'a Path,
+    hide_untracked: bool,
+    include_ignored: bool,
 }
 
-// Pre-condition:
-//   - all path in relative path without ./ prefix
-//   - directory name ends with /
-fn report_tracking_status<I>(file_names: I, tracked: &HashMap<String, u32>)
-where
-    I: IntoIterator,
-    I::Item: AsRef<str>,
-{
-    for file_name in file_names {
-        let file_name = String::from(file_name.as_ref());
-        if file_name.ends_with("/") {
-            let dir_name = file_name;
-            match tracked.get(&dir_name) {
-                Some(&count) => report_dir_with_tracked_files(&dir_name, count),
-                None => report_dir_without_tracked_files(&dir_name),
+// Pre-condition: `entries` paths are relative to `target`.
+fn report_tracking_status(
+    ctx: &ReportContext,
+    target: &Path,
+    entries: &[Entry],
+    matcher: &ignore::gitignore::Gitignore,
+) {
+    for entry in entries {
+        let depth = entry.path.components().count().saturating_sub(1);
+        let indent = "  ".repeat(depth);
+        let name = entry
+            .path
+            .file_name()
+            .expect("walked entry should have a file name")
+            .to_string_lossy();
+
+        // `target` and `ctx.work_tree` are both canonicalized in `main`, so
+        // this only fails when `target` lies outside the work tree
+        // entirely — in which case nothing under it can be tracked, and
+        // falling back to the absolute path (rather than `entry.path`)
+        // guarantees it won't accidentally collide with an unrelated
+        // tracked path that happens to share the same relative suffix.
+        let absolute_path = target.join(&entry.path);
+        let work_tree_relative = absolute_path
+            .strip_prefix(ctx.work_tree)
+            .unwrap_or(&absolute_path);
+
+        if entry.is_dir {
+            match ctx.tracked.tracked_count_under(work_tree_relative) {
+                Some(count) => ctx.formatter.dir_with_tracked_files(&indent, &name, count),
+                None if gitignore::is_ignored(matcher, work_tree_relative, true) => {
+                    if ctx.include_ignored {
+                        ctx.formatter.ignored(&indent, &name, true);
+                    }
+                }
+                None => ctx.formatter.dir_without_tracked_files(&indent, &name),
             }
-        } else {
-            match tracked.get(&file_name) {
-                Some(&count) if count == 1 => report_tracked_file(&file_name),
-                Some(_) => unreachable!("file should have track count of 1"),
-                None => report_untracked_file(&file_name),
+        } else if let Some(&status) = ctx.statuses.get(work_tree_relative) {
+            // A path can carry a `FileStatus` (e.g. `Staged` for a new
+            // file that's been `git add`ed) without ever having been
+            // committed, so it won't be in `tracked` — check statuses
+            // first rather than gating on HEAD membership.
+            ctx.formatter.tracked_file(&indent, &name, status);
+        } else if ctx.tracked.is_tracked_file(work_tree_relative) {
+            ctx.formatter
+                .tracked_file(&indent, &name, FileStatus::Clean);
+        } else if gitignore::is_ignored(matcher, work_tree_relative, false) {
+            if ctx.include_ignored {
+                ctx.formatter.ignored(&indent, &name, false);
             }
+        } else if !ctx.hide_untracked {
+            ctx.formatter.untracked_file(&indent, &name);
         }
     }
 }
-
-fn report_dir_with_tracked_files(dir_name: &str, count: u32) {
-    println!("{} - {}", dir_name, count);
-}
-
-fn report_dir_without_tracked_files(dir_name: &str) {
-    println!("{} - None", dir_name);
-}
-
-fn report_tracked_file(file_name: &str) {
-    println!("{} - CHECKED", file_name);
-}
-
-fn report_untracked_file(file_name: &str) {
-    println!("{} - LEFT", file_name);
-}
-
-#[allow(dead_code)]
-fn print_files<I>(iter: I)
-where
-    I: IntoIterator,
-    I::Item: AsRef<str>,
-{
-    for file_name in iter {
-        println!("{:?}", file_name.as_ref());
-    }
-}
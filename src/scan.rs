@@ -0,0 +1,188 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+use anyhow::{Context, Result};
+use rayon::{Scope, ThreadPoolBuilder};
+
+/// A single entry discovered while walking a target directory, relative to
+/// that directory's root.
+pub struct Entry {
+    pub path: PathBuf,
+    pub is_dir: bool,
+}
+
+/// Recursively list the contents of `dir`, honoring an optional depth cap.
+///
+/// Traversal fans out over a rayon thread pool: each directory is its own
+/// unit of work that emits an `Entry` per child and, for subdirectories
+/// still within `max_depth`, spawns more work back into the same pool —
+/// mirroring a recursive work-queue walker rather than a single serial
+/// pass. `threads` pins the pool size; `Some(1)` gives a deterministic
+/// single-threaded fallback. Results are sorted by path before returning,
+/// so output order doesn't depend on how work happened to interleave.
+pub fn get_file_names<P: AsRef<Path>>(
+    dir: P,
+    max_depth: Option<usize>,
+    threads: Option<usize>,
+) -> Result<Vec<Entry>> {
+    let dir = dir.as_ref();
+
+    let mut builder = ThreadPoolBuilder::new();
+    if let Some(threads) = threads {
+        builder = builder.num_threads(threads);
+    }
+    let pool = builder
+        .build()
+        .context("Failed to build scanning thread pool")?;
+
+    let (tx, rx) = mpsc::channel();
+    pool.scope(|scope| walk(scope, dir, dir.to_path_buf(), 1, max_depth, tx));
+
+    let mut entries: Vec<Entry> = rx.into_iter().collect();
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(entries)
+}
+
+fn walk<'scope>(
+    scope: &Scope<'scope>,
+    root: &'scope Path,
+    dir: PathBuf,
+    depth: usize,
+    max_depth: Option<usize>,
+    tx: mpsc::Sender<Entry>,
+) {
+    let Ok(read_dir) = fs::read_dir(&dir) else {
+        return;
+    };
+
+    for dir_entry in read_dir.filter_map(|entry| entry.ok()) {
+        let path = dir_entry.path();
+        let Ok(relative) = path.strip_prefix(root) else {
+            continue;
+        };
+        let is_dir = dir_entry
+            .file_type()
+            .map(|file_type| file_type.is_dir())
+            .unwrap_or(false);
+
+        let _ = tx.send(Entry {
+            path: relative.to_path_buf(),
+            is_dir,
+        });
+
+        if is_dir && max_depth.is_none_or(|limit| depth < limit) {
+            let tx = tx.clone();
+            scope.spawn(move |scope| walk(scope, root, path, depth + 1, max_depth, tx));
+        }
+    }
+}
+
+/// The set of paths tracked by the bare repo, indexed for both exact file
+/// lookups and per-directory aggregate counts.
+pub struct TrackedIndex {
+    files: HashSet<PathBuf>,
+    dir_counts: HashMap<PathBuf, u32>,
+}
+
+impl TrackedIndex {
+    /// Build an index from the full set of tracked paths (relative to the
+    /// work tree root).
+    pub fn build<I>(tracked_file_names: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<Path>,
+    {
+        let mut files = HashSet::new();
+        let mut dir_counts = HashMap::new();
+
+        for name in tracked_file_names {
+            let path = name.as_ref().to_path_buf();
+
+            let mut ancestor = PathBuf::new();
+            let mut components = path.components().peekable();
+            while let Some(component) = components.next() {
+                if components.peek().is_none() {
+                    break;
+                }
+                ancestor.push(component);
+                *dir_counts.entry(ancestor.clone()).or_insert(0) += 1;
+            }
+
+            files.insert(path);
+        }
+
+        TrackedIndex { files, dir_counts }
+    }
+
+    pub fn is_tracked_file(&self, path: &Path) -> bool {
+        self.files.contains(path)
+    }
+
+    pub fn tracked_count_under(&self, dir: &Path) -> Option<u32> {
+        self.dir_counts.get(dir).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted_paths(entries: &[Entry]) -> Vec<&Path> {
+        entries.iter().map(|entry| entry.path.as_path()).collect()
+    }
+
+    fn sample_tree() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        fs::create_dir_all(dir.path().join("a/b")).unwrap();
+        fs::write(dir.path().join("top.txt"), "").unwrap();
+        fs::write(dir.path().join("a/mid.txt"), "").unwrap();
+        fs::write(dir.path().join("a/b/deep.txt"), "").unwrap();
+        dir
+    }
+
+    #[test]
+    fn unlimited_depth_finds_every_entry_single_threaded() {
+        let dir = sample_tree();
+        let entries = get_file_names(dir.path(), None, Some(1)).expect("walk should succeed");
+
+        assert_eq!(
+            sorted_paths(&entries),
+            vec![
+                Path::new("a"),
+                Path::new("a/b"),
+                Path::new("a/b/deep.txt"),
+                Path::new("a/mid.txt"),
+                Path::new("top.txt"),
+            ]
+        );
+    }
+
+    #[test]
+    fn level_caps_recursion_depth() {
+        let dir = sample_tree();
+        let entries = get_file_names(dir.path(), Some(1), Some(1)).expect("walk should succeed");
+
+        assert_eq!(
+            sorted_paths(&entries),
+            vec![Path::new("a"), Path::new("top.txt")]
+        );
+    }
+
+    #[test]
+    fn output_is_sorted_regardless_of_thread_count() {
+        let dir = sample_tree();
+        let single_threaded =
+            get_file_names(dir.path(), None, Some(1)).expect("walk should succeed");
+        let multi_threaded = get_file_names(dir.path(), None, None).expect("walk should succeed");
+
+        assert_eq!(
+            sorted_paths(&single_threaded),
+            sorted_paths(&multi_threaded)
+        );
+        assert!(single_threaded
+            .windows(2)
+            .all(|pair| pair[0].path <= pair[1].path));
+    }
+}
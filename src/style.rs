@@ -0,0 +1,112 @@
+use std::io::IsTerminal;
+
+use colored::{Color, Colorize};
+
+use crate::cli::Cli;
+use crate::git::FileStatus;
+
+/// Glyphs used for each reported status. Every field is overridable from the
+/// CLI so users can pick their own character set.
+pub struct Symbols {
+    pub clean: String,
+    pub untracked: String,
+    pub modified: String,
+    pub staged: String,
+    pub deleted: String,
+    pub renamed: String,
+    pub ignored: String,
+}
+
+impl Default for Symbols {
+    fn default() -> Self {
+        Symbols {
+            clean: "✓".to_string(),
+            untracked: "✗".to_string(),
+            modified: "!".to_string(),
+            staged: "+".to_string(),
+            deleted: "-".to_string(),
+            renamed: "→".to_string(),
+            ignored: "○".to_string(),
+        }
+    }
+}
+
+impl Symbols {
+    pub fn from_cli(cli: &Cli) -> Self {
+        let defaults = Symbols::default();
+        Symbols {
+            clean: cli.symbol_clean.clone().unwrap_or(defaults.clean),
+            untracked: cli.symbol_untracked.clone().unwrap_or(defaults.untracked),
+            modified: cli.symbol_modified.clone().unwrap_or(defaults.modified),
+            staged: cli.symbol_staged.clone().unwrap_or(defaults.staged),
+            deleted: cli.symbol_deleted.clone().unwrap_or(defaults.deleted),
+            renamed: cli.symbol_renamed.clone().unwrap_or(defaults.renamed),
+            ignored: cli.symbol_ignored.clone().unwrap_or(defaults.ignored),
+        }
+    }
+}
+
+/// Renders report lines, applying color and the configured glyph set.
+///
+/// All output from `report_tracking_status` should go through this so the
+/// whole report stays styled consistently.
+pub struct Formatter {
+    symbols: Symbols,
+    color: bool,
+}
+
+impl Formatter {
+    pub fn new(symbols: Symbols, no_color: bool) -> Self {
+        let color = !no_color && std::io::stdout().is_terminal();
+        Formatter { symbols, color }
+    }
+
+    fn paint(&self, text: &str, color: Color) -> String {
+        if self.color {
+            text.color(color).to_string()
+        } else {
+            text.to_string()
+        }
+    }
+
+    pub fn tracked_file(&self, indent: &str, name: &str, status: FileStatus) {
+        let (glyph, color) = match status {
+            FileStatus::Clean => (self.symbols.clean.as_str(), Color::Green),
+            FileStatus::Modified => (self.symbols.modified.as_str(), Color::Yellow),
+            FileStatus::Staged => (self.symbols.staged.as_str(), Color::Cyan),
+            FileStatus::Deleted => (self.symbols.deleted.as_str(), Color::Red),
+            FileStatus::Renamed => (self.symbols.renamed.as_str(), Color::Magenta),
+        };
+        println!("{}{} {}", indent, self.paint(glyph, color), name);
+    }
+
+    pub fn untracked_file(&self, indent: &str, name: &str) {
+        println!(
+            "{}{} {}",
+            indent,
+            self.paint(&self.symbols.untracked, Color::Red),
+            name
+        );
+    }
+
+    pub fn dir_with_tracked_files(&self, indent: &str, name: &str, count: u32) {
+        let badge = self.paint(&format!("({})", count), Color::Blue);
+        println!("{}{}/ {}", indent, name, badge);
+    }
+
+    pub fn dir_without_tracked_files(&self, indent: &str, name: &str) {
+        let badge = self.paint("(none)", Color::BrightBlack);
+        println!("{}{}/ {}", indent, name, badge);
+    }
+
+    pub fn ignored(&self, indent: &str, name: &str, is_dir: bool) {
+        let suffix = if is_dir { "/" } else { "" };
+        println!(
+            "{}{}{} {}",
+            indent,
+            name,
+            suffix,
+            self.paint(&self.symbols.ignored, Color::BrightBlack)
+        );
+    }
+}